@@ -1,5 +1,9 @@
-use std::{env, path::PathBuf, process};
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fs;
+use std::{env, path::{Path, PathBuf}, process};
 
+mod meta_parser;
 mod models;
 mod parser;
 mod updater;
@@ -37,38 +41,88 @@ pub struct Config {
 }
 
 impl Config {
-    fn new(args: &[String]) -> Result<Config, &'static str> {
+    fn new(args: &[String]) -> Result<Config, String> {
         if args.len() == 1 || args[1] == "--help" || args[1] == "-h" {
             println!("STFU LOSER BITCH");
             process::exit(0);
         }
 
-        if args.len() < 3 {
-            return Err("not enough arguments");
+        // Split the CLI args into flags (which may appear anywhere) and positionals.
+        let mut positional: Vec<&str> = Vec::new();
+        let mut use_fast = false;
+        let mut config_path: Option<PathBuf> = None;
+        let mut i = 1;
+        while i < args.len() {
+            match args[i].as_str() {
+                "--fast" | "-f" => use_fast = true,
+                "--config" | "-c" => {
+                    i += 1;
+                    let path = args
+                        .get(i)
+                        .ok_or_else(|| String::from("--config requires a path"))?;
+                    config_path = Some(PathBuf::from(path));
+                }
+                other => positional.push(other),
+            }
+            i += 1;
+        }
+
+        // File values seed the config; CLI positionals and flags win over them.
+        let dataset_cli = positional.first().map(|s| PathBuf::from(s));
+
+        // A config file is used when passed explicitly, or auto-discovered next to the dataset.
+        if config_path.is_none() {
+            if let Some(dataset) = &dataset_cli {
+                if let Some(parent) = dataset.parent() {
+                    let candidate = parent.join("teriyaki.conf");
+                    if candidate.exists() {
+                        config_path = Some(candidate);
+                    }
+                }
+            }
         }
 
-        let dataset_path = PathBuf::from(&args[1]);
+        let file_values = match &config_path {
+            Some(path) => parse_config_file(path)?,
+            None => HashMap::new(),
+        };
+
+        let dataset_path = dataset_cli
+            .or_else(|| file_values.get("dataset").map(PathBuf::from))
+            .ok_or_else(|| String::from("not enough arguments"))?;
         if !dataset_path.exists() {
-            return Err("dataset path does not exist");
+            return Err(String::from("dataset path does not exist"));
         }
 
-        let update_path = PathBuf::from(&args[2]);
+        let update_path = positional
+            .get(1)
+            .map(PathBuf::from)
+            .or_else(|| file_values.get("update").map(PathBuf::from))
+            .ok_or_else(|| String::from("not enough arguments"))?;
         if !update_path.exists() {
-            return Err("update path does not exist");
+            return Err(String::from("update path does not exist"));
         }
 
-        let meta_folder_path = PathBuf::from(&args[3]);
+        let meta_folder_path = positional
+            .get(2)
+            .map(PathBuf::from)
+            .or_else(|| file_values.get("meta").map(PathBuf::from))
+            .ok_or_else(|| String::from("not enough arguments"))?;
 
-        let mut use_fast = false;
-        if args.len() > 4 && (args[4] == "--fast" || args[4] == "-f") {
+        // `--fast` on the CLI forces fast mode; otherwise honour the file's `fast` key.
+        if !use_fast {
+            if let Some(value) = file_values.get("fast") {
+                use_fast = matches!(value.as_str(), "true" | "1" | "yes" | "on");
+            }
+        }
+        if use_fast {
             println!("[ANON] GAMER MODE ACTIVATED _  _ _ xX_Using fast mode_Xx");
-            use_fast = true;
         }
 
         if use_fast && meta_folder_path.exists() {
-            return Err("Using fast mode and meta folder path already exists");
+            return Err(String::from("Using fast mode and meta folder path already exists"));
         } else if !use_fast && !meta_folder_path.exists() {
-            return Err("using slow mode and meta folder path does not exist");
+            return Err(String::from("using slow mode and meta folder path does not exist"));
         }
 
         Ok(Config {
@@ -79,3 +133,89 @@ impl Config {
         })
     }
 }
+
+/// Parses an INI-style config file into a flat `key -> value` map.
+///
+/// Supports `[section]` headers (keys inside become `section.key`), `key = value` items with
+/// surrounding whitespace trimmed, `;`/`#` line comments, a `%include <path>` directive that
+/// recursively splices another file relative to the including file, and a `%unset <key>`
+/// directive that drops a previously-set key so a later include can override an earlier one.
+fn parse_config_file(path: &Path) -> Result<HashMap<String, String>, String> {
+    let mut values: HashMap<String, String> = HashMap::new();
+    let mut visited: HashSet<PathBuf> = HashSet::new();
+    parse_config_into(path, &mut values, &mut visited)?;
+    Ok(values)
+}
+
+fn parse_config_into(
+    path: &Path,
+    values: &mut HashMap<String, String>,
+    visited: &mut HashSet<PathBuf>,
+) -> Result<(), String> {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if !visited.insert(canonical.clone()) {
+        return Err(format!("{}: recursive %include detected", path.display()));
+    }
+
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut section = String::new();
+
+    for (idx, raw) in contents.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = raw.trim();
+
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("%include") {
+            let included = rest.trim();
+            if included.is_empty() {
+                return Err(format!("{}:{}: %include requires a path", path.display(), line_no));
+            }
+            parse_config_into(&dir.join(included), values, visited)?;
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("%unset") {
+            let key = rest.trim();
+            if key.is_empty() {
+                return Err(format!("{}:{}: %unset requires a key", path.display(), line_no));
+            }
+            values.remove(&qualify(&section, key));
+            continue;
+        }
+
+        if let Some(inner) = line.strip_prefix('[') {
+            let name = inner
+                .strip_suffix(']')
+                .ok_or_else(|| format!("{}:{}: unterminated section header", path.display(), line_no))?;
+            section = name.trim().to_string();
+            continue;
+        }
+
+        match line.split_once('=') {
+            Some((key, value)) => {
+                values.insert(qualify(&section, key.trim()), value.trim().to_string());
+            }
+            None => {
+                return Err(format!("{}:{}: expected 'key = value'", path.display(), line_no));
+            }
+        }
+    }
+
+    // Allow the same file to be included again along a different (non-cyclic) path.
+    visited.remove(&canonical);
+    Ok(())
+}
+
+/// Qualifies `key` with its enclosing `section`, leaving top-level keys bare.
+fn qualify(section: &str, key: &str) -> String {
+    if section.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", section, key)
+    }
+}
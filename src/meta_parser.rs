@@ -1,14 +1,33 @@
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::fs;
 use std::io;
+use std::mem;
+use std::path::Path;
 use serde::{Deserialize, Serialize};
 
+#[derive(Clone)]
 pub struct NodeInfo {
     parent: Option<u32>,
     incoming: Vec<Vec<u32>>,
     outgoing: Vec<Vec<u32>>
 }
 
+impl NodeInfo {
+    /// Returns this node's parent in the supernode hierarchy, if any.
+    pub fn parent(&self) -> Option<u32> {
+        self.parent
+    }
+}
+
+/// Resolves the parent id of a node in the supernode hierarchy.
+///
+/// Implemented by both the eagerly-parsed [`Meta`](crate::models::meta::Meta) table and the lazy
+/// [`MetaV2`] reader so the [`Ancestors`] walk can run over either.
+pub trait ParentLookup {
+    fn parent(&self, id: u32) -> Option<u32>;
+}
+
 pub(crate) fn parse_meta(path: &str) -> Result<(HashMap<u32, Vec<u32>>, HashMap<u32, NodeInfo>), io::Error> {
     let file_str = fs::read_to_string(path)?;
     let file_data: MetaFile = serde_json::from_str(&file_str)?;
@@ -45,4 +64,498 @@ struct Node {
 struct Supernode {
     i: u32,
     g: Vec<u32>
-}
\ No newline at end of file
+}
+
+// ---------------------------------------------------------------------------
+// Binary "v2" meta format
+// ---------------------------------------------------------------------------
+//
+// The JSON reader above eagerly materialises the whole node/supernode table,
+// which forces the entire file into memory even when the updater only touches a
+// handful of nodes. The binary format below is a fixed-layout, zero-copy disk
+// representation that is parsed by buffering the bytes and slicing the fixed
+// record structs in place. Individual `NodeInfo`s are only rebuilt on first
+// access and cached, so startup cost is O(number of nodes actually queried)
+// rather than O(file size).
+//
+// Layout:
+//   [Header]
+//   [NodeRecord; node_count]        sorted ascending by id
+//   [SupernodeRecord; snode_count]  sorted ascending by id
+//   [node edge pool : u32]          incoming/outgoing adjacency, length-prefixed
+//   [supernode pool : u32]          flat member lists
+//
+// Adjacency lists (`Vec<Vec<u32>>`) are encoded in the edge pool as a group
+// count followed by, per group, a group length and that many ids. A record's
+// `*_len` counts the total number of `u32`s the list occupies in the pool.
+
+const MAGIC: [u8; 4] = *b"TMV2";
+const VERSION: u32 = 2;
+
+const FLAG_IS_SUPERNODE: u32 = 1 << 0;
+const FLAG_HAS_PARENT: u32 = 1 << 1;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Header {
+    magic: [u8; 4],
+    version: u32,
+    node_count: u32,
+    snode_count: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct NodeRecord {
+    id: u32,
+    parent: u32,
+    flags: u32,
+    in_offset: u32,
+    in_len: u32,
+    out_offset: u32,
+    out_len: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct SupernodeRecord {
+    id: u32,
+    offset: u32,
+    len: u32,
+}
+
+/// A lazily-parsed, memory-resident view over a binary `v2` meta file.
+///
+/// The backing bytes are read once; `NodeInfo`s are decoded on demand and cached
+/// so repeated lookups are cheap and untouched nodes are never decoded at all.
+pub struct MetaV2 {
+    bytes: Vec<u8>,
+    node_count: usize,
+    snode_count: usize,
+    records_offset: usize,
+    snode_records_offset: usize,
+    node_pool_offset: usize,
+    snode_pool_offset: usize,
+    node_cache: RefCell<HashMap<u32, NodeInfo>>,
+    snode_cache: RefCell<HashMap<u32, Vec<u32>>>,
+}
+
+impl MetaV2 {
+    /// Opens a binary meta file by buffering its bytes.
+    ///
+    /// (A memory-mapped backing would drop in here unchanged; the record slicing
+    /// below only ever reads through `&self.bytes`.)
+    pub fn open(path: &str) -> Result<Self, io::Error> {
+        let bytes = fs::read(path)?;
+        Self::from_bytes(bytes)
+    }
+
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, io::Error> {
+        if bytes.len() < mem::size_of::<Header>() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "meta: truncated header"));
+        }
+
+        let header: Header = read_record(&bytes, 0);
+        if header.magic != MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "meta: bad magic"));
+        }
+        if header.version != VERSION {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "meta: unsupported version"));
+        }
+
+        let node_count = header.node_count as usize;
+        let snode_count = header.snode_count as usize;
+
+        let records_offset = mem::size_of::<Header>();
+        let snode_records_offset = records_offset + node_count * mem::size_of::<NodeRecord>();
+        let node_pool_offset = snode_records_offset + snode_count * mem::size_of::<SupernodeRecord>();
+
+        // The header counts are untrusted: verify the record tables actually fit before scanning
+        // them, so the reads in `node_pool_len`/`snode_pool_len` below cannot run off the end.
+        if node_pool_offset > bytes.len() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "meta: record table exceeds file"));
+        }
+
+        // The supernode pool begins right after the node pool; derive the split from the records
+        // rather than trusting a redundant header field, then bounds-check both pools.
+        let snode_pool_offset = node_pool_offset + node_pool_len(&bytes, records_offset, node_count)?;
+        if snode_pool_offset > bytes.len() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "meta: node edge pool exceeds file"));
+        }
+
+        let snode_pool_end =
+            snode_pool_offset + snode_pool_len(&bytes, snode_records_offset, snode_count)?;
+        if snode_pool_end > bytes.len() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "meta: supernode pool exceeds file"));
+        }
+
+        Ok(Self {
+            bytes,
+            node_count,
+            snode_count,
+            records_offset,
+            snode_records_offset,
+            node_pool_offset,
+            snode_pool_offset,
+            node_cache: RefCell::new(HashMap::new()),
+            snode_cache: RefCell::new(HashMap::new()),
+        })
+    }
+
+    /// Resolves the `NodeInfo` for `id`, decoding and caching it on first access.
+    ///
+    /// Returns `Ok(None)` when `id` is absent and `InvalidData` when the backing record is corrupt.
+    pub fn get_node(&self, id: u32) -> Result<Option<NodeInfo>, io::Error> {
+        if let Some(info) = self.node_cache.borrow().get(&id) {
+            return Ok(Some(info.clone()));
+        }
+
+        let rec = match self.find_node_record(id) {
+            Some(rec) => rec,
+            None => return Ok(None),
+        };
+        let incoming = decode_groups(&self.bytes, self.node_pool_offset, rec.in_offset, rec.in_len)?;
+        let outgoing = decode_groups(&self.bytes, self.node_pool_offset, rec.out_offset, rec.out_len)?;
+        let parent = if rec.flags & FLAG_HAS_PARENT != 0 {
+            Some(rec.parent)
+        } else {
+            None
+        };
+
+        let info = NodeInfo { parent, incoming, outgoing };
+        self.node_cache.borrow_mut().insert(id, info.clone());
+        Ok(Some(info))
+    }
+
+    /// Resolves the members of the supernode `id`, caching the result.
+    pub fn get_supernode(&self, id: u32) -> Option<Vec<u32>> {
+        if let Some(members) = self.snode_cache.borrow().get(&id) {
+            return Some(members.clone());
+        }
+
+        let rec = self.find_snode_record(id)?;
+        let members = read_u32_slice(&self.bytes, self.snode_pool_offset, rec.offset, rec.len);
+        self.snode_cache.borrow_mut().insert(id, members.clone());
+        Some(members)
+    }
+
+    /// Binary-searches the id-sorted node record table.
+    fn find_node_record(&self, id: u32) -> Option<NodeRecord> {
+        find_record(&self.bytes, self.records_offset, self.node_count, id)
+    }
+
+    /// Binary-searches the id-sorted supernode record table.
+    fn find_snode_record(&self, id: u32) -> Option<SupernodeRecord> {
+        find_record(&self.bytes, self.snode_records_offset, self.snode_count, id)
+    }
+}
+
+/// A fixed-layout record keyed by an ascending `id`, searchable by [`find_record`].
+trait IdKeyed: Copy {
+    fn id(&self) -> u32;
+}
+
+impl IdKeyed for NodeRecord {
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+impl IdKeyed for SupernodeRecord {
+    fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+/// Binary-searches an id-sorted record table of `count` `T`s starting at `base`.
+fn find_record<T: IdKeyed>(bytes: &[u8], base: usize, count: usize, id: u32) -> Option<T> {
+    let (mut lo, mut hi) = (0usize, count);
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        let rec: T = read_record(bytes, base + mid * mem::size_of::<T>());
+        let rec_id = rec.id();
+        if rec_id == id {
+            return Some(rec);
+        } else if rec_id < id {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    None
+}
+
+/// Reads a `Copy` record out of `bytes` at `offset` with an unaligned load.
+///
+/// The record types are `#[repr(C)]` plain-old-data, so a raw read is sound for
+/// any byte offset regardless of alignment.
+fn read_record<T: Copy>(bytes: &[u8], offset: usize) -> T {
+    assert!(offset + mem::size_of::<T>() <= bytes.len(), "meta: record out of bounds");
+    // SAFETY: `T` is a `#[repr(C)]` POD type, the range is bounds-checked above, and the read
+    // is unaligned so the arbitrary byte offset is fine.
+    unsafe { std::ptr::read_unaligned(bytes.as_ptr().add(offset) as *const T) }
+}
+
+/// Reads `len` `u32`s starting `offset` `u32`s into the pool at `pool_offset`.
+fn read_u32_slice(bytes: &[u8], pool_offset: usize, offset: u32, len: u32) -> Vec<u32> {
+    let start = pool_offset + offset as usize * mem::size_of::<u32>();
+    let mut out = Vec::with_capacity(len as usize);
+    for i in 0..len as usize {
+        out.push(read_record::<u32>(bytes, start + i * mem::size_of::<u32>()));
+    }
+    out
+}
+
+/// Decodes a length-prefixed group list (`Vec<Vec<u32>>`) out of the pool.
+///
+/// A group count or length that walks past the record's span signals a corrupt file and returns
+/// `InvalidData`, matching the rest of the reader's error discipline rather than silently
+/// truncating the adjacency list.
+fn decode_groups(bytes: &[u8], pool_offset: usize, offset: u32, len: u32) -> Result<Vec<Vec<u32>>, io::Error> {
+    if len == 0 {
+        return Ok(Vec::new());
+    }
+    let flat = read_u32_slice(bytes, pool_offset, offset, len);
+    let mut groups: Vec<Vec<u32>> = Vec::new();
+    let group_count = flat[0] as usize;
+    let mut cursor = 1;
+    for _ in 0..group_count {
+        let group_len = *flat
+            .get(cursor)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "meta: truncated group list"))?
+            as usize;
+        cursor += 1;
+        let end = cursor + group_len;
+        if end > flat.len() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "meta: group length exceeds record span"));
+        }
+        groups.push(flat[cursor..end].to_vec());
+        cursor = end;
+    }
+    Ok(groups)
+}
+
+/// Scans the node record table to find the total length of the node edge pool, so the supernode
+/// pool offset can be derived without trusting a redundant header field.
+///
+/// The offset/length pairs come from untrusted records, so their sums are checked for overflow.
+fn node_pool_len(bytes: &[u8], records_offset: usize, node_count: usize) -> Result<usize, io::Error> {
+    let mut max_end: u32 = 0;
+    for i in 0..node_count {
+        let rec: NodeRecord = read_record(bytes, records_offset + i * mem::size_of::<NodeRecord>());
+        max_end = max_end
+            .max(checked_span_end(rec.in_offset, rec.in_len)?)
+            .max(checked_span_end(rec.out_offset, rec.out_len)?);
+    }
+    Ok(max_end as usize * mem::size_of::<u32>())
+}
+
+/// Scans the supernode record table to find the total length of the supernode pool.
+fn snode_pool_len(bytes: &[u8], records_offset: usize, snode_count: usize) -> Result<usize, io::Error> {
+    let mut max_end: u32 = 0;
+    for i in 0..snode_count {
+        let rec: SupernodeRecord =
+            read_record(bytes, records_offset + i * mem::size_of::<SupernodeRecord>());
+        max_end = max_end.max(checked_span_end(rec.offset, rec.len)?);
+    }
+    Ok(max_end as usize * mem::size_of::<u32>())
+}
+
+/// Returns `offset + len`, or an `InvalidData` error if the untrusted pair overflows `u32`.
+fn checked_span_end(offset: u32, len: u32) -> Result<u32, io::Error> {
+    offset
+        .checked_add(len)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "meta: pool span overflow"))
+}
+
+/// Converts an old JSON meta file into the binary `v2` format.
+///
+/// Existing `.json` meta folders can be upgraded in place by pointing this at each file and
+/// writing the result next to it; the JSON reader ([`parse_meta`]) stays available as the slow
+/// path for unconverted folders.
+pub fn convert_json_to_v2(json_path: &str, out_path: &Path) -> Result<(), io::Error> {
+    let (supernodes, nodes) = parse_meta(json_path)?;
+
+    // Sort both tables by id so the reader can binary-search them.
+    let mut node_ids: Vec<u32> = nodes.keys().copied().collect();
+    node_ids.sort_unstable();
+    let mut snode_ids: Vec<u32> = supernodes.keys().copied().collect();
+    snode_ids.sort_unstable();
+
+    let mut node_records: Vec<NodeRecord> = Vec::with_capacity(node_ids.len());
+    let mut node_pool: Vec<u32> = Vec::new();
+    for id in &node_ids {
+        let info = &nodes[id];
+        let in_offset = node_pool.len() as u32;
+        encode_groups(&mut node_pool, &info.incoming);
+        let in_len = node_pool.len() as u32 - in_offset;
+
+        let out_offset = node_pool.len() as u32;
+        encode_groups(&mut node_pool, &info.outgoing);
+        let out_len = node_pool.len() as u32 - out_offset;
+
+        let mut flags = 0;
+        if info.parent.is_some() {
+            flags |= FLAG_HAS_PARENT;
+        }
+        if supernodes.contains_key(id) {
+            flags |= FLAG_IS_SUPERNODE;
+        }
+
+        node_records.push(NodeRecord {
+            id: *id,
+            parent: info.parent.unwrap_or(0),
+            flags,
+            in_offset,
+            in_len,
+            out_offset,
+            out_len,
+        });
+    }
+
+    let mut snode_records: Vec<SupernodeRecord> = Vec::with_capacity(snode_ids.len());
+    let mut snode_pool: Vec<u32> = Vec::new();
+    for id in &snode_ids {
+        let members = &supernodes[id];
+        let offset = snode_pool.len() as u32;
+        snode_pool.extend_from_slice(members);
+        snode_records.push(SupernodeRecord {
+            id: *id,
+            offset,
+            len: members.len() as u32,
+        });
+    }
+
+    let header = Header {
+        magic: MAGIC,
+        version: VERSION,
+        node_count: node_records.len() as u32,
+        snode_count: snode_records.len() as u32,
+    };
+
+    let mut out: Vec<u8> = Vec::new();
+    push_record(&mut out, &header);
+    for rec in &node_records {
+        push_record(&mut out, rec);
+    }
+    for rec in &snode_records {
+        push_record(&mut out, rec);
+    }
+    for w in &node_pool {
+        out.extend_from_slice(&w.to_ne_bytes());
+    }
+    for w in &snode_pool {
+        out.extend_from_slice(&w.to_ne_bytes());
+    }
+
+    fs::write(out_path, out)
+}
+
+/// Appends the raw bytes of a `Copy` record to `out`.
+fn push_record<T: Copy>(out: &mut Vec<u8>, record: &T) {
+    // SAFETY: `T` is a `#[repr(C)]` POD type; we only read its bytes.
+    let bytes = unsafe {
+        std::slice::from_raw_parts((record as *const T) as *const u8, mem::size_of::<T>())
+    };
+    out.extend_from_slice(bytes);
+}
+
+impl MetaV2 {
+    /// Lazily enumerates every supernode the `start_ids` roll up into.
+    ///
+    /// See [`Ancestors::new`] for the `inclusive`/`stop_at` semantics.
+    pub fn ancestors(&self, start_ids: &[u32], inclusive: bool, stop_at: Option<u32>) -> Ancestors<'_, Self> {
+        Ancestors::new(self, start_ids, inclusive, stop_at)
+    }
+}
+
+impl ParentLookup for MetaV2 {
+    /// Reads only the fixed node record, so this stays cheap even for nodes whose full
+    /// `NodeInfo` has never been decoded.
+    fn parent(&self, id: u32) -> Option<u32> {
+        let rec = self.find_node_record(id)?;
+        if rec.flags & FLAG_HAS_PARENT != 0 {
+            Some(rec.parent)
+        } else {
+            None
+        }
+    }
+}
+
+/// A lazy iterator over all supernodes a set of starting ids rolls up into.
+///
+/// The meta layer nests supernodes via `NodeInfo::parent`, forming an implicit ancestor
+/// tree/DAG. `Ancestors` walks those parent pointers (resolved through any [`ParentLookup`])
+/// without materialising the whole chain: a [`BinaryHeap`] keeps the frontier max-ordered by id
+/// and a [`HashSet`] of already-yielded ids guarantees each ancestor is visited exactly once,
+/// even when several start ids share an ancestor. Visiting in descending-id order keeps the
+/// traversal deterministic.
+///
+/// Build one via [`Meta::ancestors`](crate::models::meta::Meta::ancestors) or
+/// [`MetaV2::ancestors`] so the updater and writer can ask "what does this node collapse into".
+pub struct Ancestors<'a, P: ParentLookup> {
+    heap: BinaryHeap<u32>,
+    seen: HashSet<u32>,
+    lookup: &'a P,
+    stop_at: Option<u32>,
+}
+
+impl<'a, P: ParentLookup> Ancestors<'a, P> {
+    /// Seeds the traversal from `start_ids`.
+    ///
+    /// When `inclusive` is true the start ids are yielded themselves; otherwise the walk begins
+    /// at their parents. If `stop_at` is set, iteration stops once ids drop below that threshold.
+    pub fn new(lookup: &'a P, start_ids: &[u32], inclusive: bool, stop_at: Option<u32>) -> Self {
+        let mut heap = BinaryHeap::new();
+        let mut seen = HashSet::new();
+
+        for &id in start_ids {
+            if inclusive {
+                if seen.insert(id) {
+                    heap.push(id);
+                }
+            } else if let Some(parent) = lookup.parent(id) {
+                if seen.insert(parent) {
+                    heap.push(parent);
+                }
+            }
+        }
+
+        Self { heap, seen, lookup, stop_at }
+    }
+}
+
+impl<P: ParentLookup> Iterator for Ancestors<'_, P> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        let id = self.heap.pop()?;
+
+        if let Some(threshold) = self.stop_at {
+            if id < threshold {
+                self.heap.clear();
+                return None;
+            }
+        }
+
+        if let Some(parent) = self.lookup.parent(id) {
+            if self.seen.insert(parent) {
+                self.heap.push(parent);
+            }
+        }
+
+        Some(id)
+    }
+}
+
+/// Length-prefix encodes a `Vec<Vec<u32>>` into the flat pool.
+fn encode_groups(pool: &mut Vec<u32>, groups: &[Vec<u32>]) {
+    if groups.is_empty() {
+        return;
+    }
+    pool.push(groups.len() as u32);
+    for group in groups {
+        pool.push(group.len() as u32);
+        pool.extend_from_slice(group);
+    }
+}
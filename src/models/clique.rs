@@ -104,20 +104,53 @@ impl CliqueCollection {
 
     /// Merges the cliques containing the ids `a` and `b`, which can either be nodes or preds.
     ///
-    /// `b`'s clique is merged into `a`'s clique, leaving `b`'s clique empty.
-    pub fn merge_cliques(&mut self, a: &u32, b: &u32) {
-        let a_index = *self.index_map.get(a).unwrap();
-        let b_index = *self.index_map.get(b).unwrap();
+    /// Uses a union-by-size heuristic: the smaller of the two cliques is relocated into the
+    /// larger one, so `index_map` is only rewritten for the members that actually moved. The
+    /// larger clique's index survives and the smaller one is recycled into `queue`. Because a
+    /// member's clique can only be rewritten when it lands in a clique at least twice as large,
+    /// the total reassignment work is bounded to O(n log n). The exception is the empty clique at
+    /// index 0: when it is one of the two, it always survives and the other clique is relocated
+    /// into it whatever its size (see [`Self::merge_target`]), so such merges fall outside that
+    /// bound.
+    ///
+    /// Returns the surviving ("winner") clique index.
+    pub fn merge_cliques(&mut self, a: &u32, b: &u32) -> usize {
+        let (winner, loser) = self.merge_target(a, b);
+
+        let loser_clique = self.cliques[loser].clone();
+        self.set_index(&loser_clique.preds, &loser_clique.nodes, winner);
 
-        let b_clique = self.cliques[b_index].clone();
-        self.set_index(&b_clique.preds, &b_clique.nodes, a_index);
+        let winner_clique = &mut self.cliques[winner];
+        winner_clique.nodes.extend(loser_clique.nodes);
+        winner_clique.preds.extend(loser_clique.preds);
 
-        let a_clique = &mut self.cliques[a_index];
+        self.remove_clique_by_index(loser);
+
+        return winner;
+    }
+
+    /// Decides which of the cliques containing `a` and `b` survives a merge.
+    ///
+    /// Returns `(winner, loser)` clique indices, where `winner` is the larger clique (by total
+    /// member count) and therefore the relocation target. The reserved empty clique at index 0
+    /// is never recycled: whenever it is involved it is forced to survive as the winner, so it
+    /// is neither chosen as the relocation target's victim nor pushed onto `queue`.
+    pub fn merge_target(&self, a: &u32, b: &u32) -> (usize, usize) {
+        let a_index = *self.index_map.get(a).unwrap();
+        let b_index = *self.index_map.get(b).unwrap();
 
-        a_clique.nodes.extend(b_clique.nodes);
-        a_clique.preds.extend(b_clique.preds);
+        // Index 0 must always survive, so it wins regardless of size when either clique is it.
+        if a_index == 0 {
+            return (a_index, b_index);
+        }
+        if b_index == 0 {
+            return (b_index, a_index);
+        }
 
-        self.remove_clique_by_index(b_index);
+        if self.clique_len(a_index) >= self.clique_len(b_index) {
+            return (a_index, b_index);
+        }
+        return (b_index, a_index);
     }
 
     /// Adds `pred` to the clique containing `node`.
@@ -226,7 +259,7 @@ impl CliqueCollection {
     }
 
     pub fn clique_len(&self, index: usize) -> usize {
-        return self.cliques[index].nodes.len();
+        return self.cliques[index].nodes.len() + self.cliques[index].preds.len();
     }
 
     pub fn move_node(&mut self, node: &u32, target: &u32) {
@@ -312,20 +345,11 @@ impl CliqueChange {
     }
 
     pub fn new_merge(cc: &CliqueCollection, a: &u32, b: &u32, is_source: bool) -> Self {
-        let a_index = cc.get_index(a);
-        let b_index = cc.get_index(b);
-
-        let change = Self::new(
-            a_index,
-            if cc.clique_len(a_index) < cc.clique_len(b_index) {
-                cc.get_nodes(a_index)
-            } else {
-                cc.get_nodes(b_index)
-            },
-            is_source,
-        );
+        // Mirror the union-by-size decision made in `merge_cliques`: the winner clique's index
+        // survives and the loser's nodes are the ones that move into it.
+        let (winner, loser) = cc.merge_target(a, b);
 
-        return change;
+        return Self::new(winner, cc.get_nodes(loser), is_source);
     }
 
     pub fn get_super_nodes(
@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+
+use crate::meta_parser::{Ancestors, NodeInfo, ParentLookup};
+
+/// The in-memory meta layer: the node table and the supernode membership map.
+///
+/// Supernodes nest via [`NodeInfo::parent`], so the table doubles as an implicit ancestor
+/// tree that [`Meta::ancestors`] walks on demand.
+pub struct Meta {
+    supernodes: HashMap<u32, Vec<u32>>,
+    nodes: HashMap<u32, NodeInfo>,
+}
+
+impl Meta {
+    /// Wraps an already-parsed supernode map and node table.
+    pub fn new(supernodes: HashMap<u32, Vec<u32>>, nodes: HashMap<u32, NodeInfo>) -> Self {
+        Self { supernodes, nodes }
+    }
+
+    /// Returns the members of the supernode `id`, if it is one.
+    pub fn get_supernode(&self, id: &u32) -> Option<&Vec<u32>> {
+        self.supernodes.get(id)
+    }
+
+    /// Lazily enumerates every supernode the `start_ids` roll up into.
+    ///
+    /// See [`Ancestors::new`] for the `inclusive`/`stop_at` semantics.
+    pub fn ancestors(&self, start_ids: &[u32], inclusive: bool, stop_at: Option<u32>) -> Ancestors<'_, Self> {
+        Ancestors::new(self, start_ids, inclusive, stop_at)
+    }
+}
+
+impl ParentLookup for Meta {
+    fn parent(&self, id: u32) -> Option<u32> {
+        self.nodes.get(&id).and_then(|info| info.parent())
+    }
+}